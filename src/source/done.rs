@@ -1,5 +1,8 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 use std::time::Duration;
 use std::sync::Mutex;
 use derivative::Derivative;
@@ -8,6 +11,16 @@ use crate::{Sample, Source};
 
 use super::SeekError;
 
+/// Why a [`Done`] source's `on_done` callback fired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DoneReason {
+    /// The inner source ran out on its own.
+    NaturalEnd,
+    /// The source was stopped early via [`AbortHandle::abort`].
+    Aborted,
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Done<I> {
@@ -15,7 +28,10 @@ pub struct Done<I> {
     signal: Arc<AtomicUsize>,
     signal_sent: bool,
     #[derivative(Debug="ignore")]
-    on_done: Arc<Mutex<Option<Box<dyn Fn() + Send + 'static>>>>,
+    on_done: Arc<Mutex<Option<Box<dyn Fn(DoneReason) + Send + 'static>>>>,
+    finished: Arc<AtomicBool>,
+    wakers: Arc<Mutex<Vec<Waker>>>,
+    aborted: Arc<AtomicBool>,
 }
 
 impl<I> Done<I> {
@@ -28,6 +44,54 @@ impl<I> Done<I> {
             signal,
             signal_sent: false,
             on_done: Arc::new(Mutex::new(None)),
+            finished: Arc::new(AtomicBool::new(false)),
+            wakers: Arc::new(Mutex::new(Vec::new())),
+            aborted: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Like [`Done::new`], but returns an [`AbortHandle`] that can be used to
+    /// stop playback of `input` early from another thread.
+    ///
+    /// Aborting behaves exactly like reaching the natural end of the source:
+    /// the `signal` is decremented and the `on_done` callback (if any) is
+    /// fired, both exactly once, regardless of whether the source ends
+    /// naturally or is aborted first.
+    #[inline]
+    pub fn abortable(input: I, signal: Arc<AtomicUsize>) -> (Done<I>, AbortHandle) {
+        let aborted = Arc::new(AtomicBool::new(false));
+        let done = Done {
+            input,
+            signal,
+            signal_sent: false,
+            on_done: Arc::new(Mutex::new(None)),
+            finished: Arc::new(AtomicBool::new(false)),
+            wakers: Arc::new(Mutex::new(Vec::new())),
+            aborted: aborted.clone(),
+        };
+        (done, AbortHandle { aborted })
+    }
+
+    /// Decrements `signal`, marks the completion future as resolved and runs
+    /// the `on_done` callback with `reason`. No-op if already called once.
+    #[inline]
+    fn fire_done(&mut self, reason: DoneReason) {
+        if self.signal_sent {
+            return;
+        }
+        self.signal.fetch_sub(1, Ordering::Relaxed);
+        self.signal_sent = true;
+        self.finished.store(true, Ordering::Release);
+        // Collect into a local Vec and drop the lock before waking: an
+        // executor may poll synchronously inside `wake()`, which would try
+        // to re-lock `wakers` on this same thread and deadlock.
+        let to_wake: Vec<Waker> = self.wakers.lock().unwrap().drain(..).collect();
+        for waker in to_wake {
+            waker.wake();
+        }
+        // Execute callback when song ends
+        if let Some(callback) = &*self.on_done.lock().unwrap() {
+            callback(reason);
         }
     }
 
@@ -50,13 +114,41 @@ impl<I> Done<I> {
     }
 
     // Add method to set callback
+    /// Sets a callback that runs when the source finishes, without regard to
+    /// why. See [`Done::set_on_done_with`] to also receive a [`DoneReason`].
     #[inline]
     pub fn set_on_done<F>(&self, callback: F)
     where
         F: Fn() + Send + 'static,
+    {
+        self.set_on_done_with(move |_reason| callback());
+    }
+
+    /// Sets a callback that runs when the source finishes, receiving the
+    /// [`DoneReason`] so callers can tell a natural end apart from an abort.
+    #[inline]
+    pub fn set_on_done_with<F>(&self, callback: F)
+    where
+        F: Fn(DoneReason) + Send + 'static,
     {
         *self.on_done.lock().unwrap() = Some(Box::new(callback));
     }
+
+    /// Returns a [`Future`] that resolves once the inner source has been
+    /// exhausted for the first time, or once the source has been stopped
+    /// through its [`AbortHandle`].
+    ///
+    /// The returned future is cheap to clone: every clone resolves at the
+    /// same point in time, so multiple tasks can independently `.await` the
+    /// end of the same track. If the source has already finished by the
+    /// time this is called, the future resolves immediately.
+    #[inline]
+    pub fn completion(&self) -> DoneFuture {
+        DoneFuture {
+            finished: self.finished.clone(),
+            wakers: self.wakers.clone(),
+        }
+    }
 }
 
 impl<I: Source> Iterator for Done<I>
@@ -68,14 +160,13 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<I::Item> {
+        if self.aborted.load(Ordering::Acquire) {
+            self.fire_done(DoneReason::Aborted);
+            return None;
+        }
         let next = self.input.next();
-        if !self.signal_sent && next.is_none() {
-            self.signal.fetch_sub(1, Ordering::Relaxed);
-            self.signal_sent = true;
-            // Execute callback when song ends
-            if let Some(callback) = &*self.on_done.lock().unwrap() {
-                callback();
-            }
+        if next.is_none() {
+            self.fire_done(DoneReason::NaturalEnd);
         }
         next
     }
@@ -115,3 +206,221 @@ where
         self.input.try_seek(pos)
     }
 }
+
+/// A handle returned by [`Done::abortable`] that stops the paired `Done`
+/// source the next time it is polled, as if its inner source had run out.
+#[derive(Clone, Debug)]
+pub struct AbortHandle {
+    aborted: Arc<AtomicBool>,
+}
+
+impl AbortHandle {
+    /// Stops the associated `Done` source. The source will report its
+    /// `signal` and fire `on_done` on its next `next()` call, same as
+    /// reaching the end naturally.
+    #[inline]
+    pub fn abort(&self) {
+        self.aborted.store(true, Ordering::Release);
+    }
+}
+
+/// A [`Future`] returned by [`Done::completion`] that resolves when the
+/// wrapped source has been exhausted or aborted.
+///
+/// Cloning a `DoneFuture` is cheap; all clones resolve together.
+#[derive(Clone, Debug)]
+pub struct DoneFuture {
+    finished: Arc<AtomicBool>,
+    wakers: Arc<Mutex<Vec<Waker>>>,
+}
+
+impl Future for DoneFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.finished.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        // Register under the wakers lock, then re-check `finished`: `fire_done`
+        // drains the wakers under the same lock, so if it raced us and already
+        // stored `finished` (Release) before we got here, this load (through
+        // the lock's Acquire) is guaranteed to observe it. Without the
+        // re-check a waker pushed between our first load and this lock could
+        // be drained-and-missed, leaving the future pending forever.
+        let mut wakers = self.wakers.lock().unwrap();
+        if self.finished.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_test::task::new_count_waker;
+
+    #[derive(Clone)]
+    struct TestSource {
+        samples: std::vec::IntoIter<f32>,
+    }
+
+    impl TestSource {
+        fn new(samples: Vec<f32>) -> Self {
+            TestSource {
+                samples: samples.into_iter(),
+            }
+        }
+    }
+
+    impl Iterator for TestSource {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<f32> {
+            self.samples.next()
+        }
+    }
+
+    impl Source for TestSource {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+
+        fn channels(&self) -> u16 {
+            1
+        }
+
+        fn sample_rate(&self) -> u32 {
+            44_100
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    #[test]
+    fn completion_future_created_after_done_resolves_immediately() {
+        let signal = Arc::new(AtomicUsize::new(1));
+        let mut done = Done::new(TestSource::new(vec![1.0]), signal);
+        assert_eq!(done.next(), Some(1.0));
+        assert_eq!(done.next(), None);
+
+        let (waker, wake_count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = done.completion();
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(()));
+        // Already resolved, so polling it must not have needed to register
+        // (and later fire) a waker.
+        assert_eq!(wake_count.count(), 0);
+    }
+
+    #[test]
+    fn completion_wakes_all_pending_futures_exactly_once() {
+        let signal = Arc::new(AtomicUsize::new(1));
+        let mut done = Done::new(TestSource::new(vec![1.0]), signal);
+
+        let (waker_a, count_a) = new_count_waker();
+        let (waker_b, count_b) = new_count_waker();
+        let mut cx_a = Context::from_waker(&waker_a);
+        let mut cx_b = Context::from_waker(&waker_b);
+
+        let mut future_a = done.completion();
+        let mut future_b = done.completion();
+        assert_eq!(Pin::new(&mut future_a).poll(&mut cx_a), Poll::Pending);
+        assert_eq!(Pin::new(&mut future_b).poll(&mut cx_b), Poll::Pending);
+
+        assert_eq!(done.next(), Some(1.0));
+        assert_eq!(done.next(), None);
+
+        // Both concurrently-awaiting futures must be woken, and each exactly
+        // once even though `fire_done` only runs a single time.
+        assert_eq!(count_a.count(), 1);
+        assert_eq!(count_b.count(), 1);
+        assert_eq!(Pin::new(&mut future_a).poll(&mut cx_a), Poll::Ready(()));
+        assert_eq!(Pin::new(&mut future_b).poll(&mut cx_b), Poll::Ready(()));
+
+        // Further `next()` calls are no-ops guarded by `signal_sent`, so they
+        // must not wake anything a second time.
+        assert_eq!(done.next(), None);
+        assert_eq!(count_a.count(), 1);
+        assert_eq!(count_b.count(), 1);
+    }
+
+    #[test]
+    fn abort_after_natural_end_decrements_signal_exactly_once() {
+        let signal = Arc::new(AtomicUsize::new(1));
+        let (mut done, handle) = Done::abortable(TestSource::new(vec![1.0]), signal.clone());
+
+        assert_eq!(done.next(), Some(1.0));
+        assert_eq!(done.next(), None); // natural end
+        assert_eq!(signal.load(Ordering::SeqCst), 0);
+
+        // Aborting after the source has already ended naturally must not
+        // decrement `signal` a second time.
+        handle.abort();
+        assert_eq!(done.next(), None);
+        assert_eq!(signal.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn natural_end_after_abort_decrements_signal_exactly_once() {
+        let signal = Arc::new(AtomicUsize::new(1));
+        let (mut done, handle) =
+            Done::abortable(TestSource::new(vec![1.0, 2.0]), signal.clone());
+
+        handle.abort();
+        assert_eq!(done.next(), None); // aborted before the source ran out
+        assert_eq!(signal.load(Ordering::SeqCst), 0);
+
+        // The inner source still has samples left, but once aborted `next()`
+        // must keep returning `None` without decrementing `signal` again.
+        assert_eq!(done.next(), None);
+        assert_eq!(signal.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn set_on_done_with_reports_natural_end() {
+        let signal = Arc::new(AtomicUsize::new(1));
+        let mut done = Done::new(TestSource::new(vec![1.0]), signal);
+        let reasons = Arc::new(Mutex::new(Vec::new()));
+        let reasons_clone = reasons.clone();
+        done.set_on_done_with(move |reason| reasons_clone.lock().unwrap().push(reason));
+
+        assert_eq!(done.next(), Some(1.0));
+        assert_eq!(done.next(), None);
+
+        assert_eq!(*reasons.lock().unwrap(), vec![DoneReason::NaturalEnd]);
+    }
+
+    #[test]
+    fn set_on_done_with_reports_aborted() {
+        let signal = Arc::new(AtomicUsize::new(1));
+        let (mut done, handle) = Done::abortable(TestSource::new(vec![1.0]), signal);
+        let reasons = Arc::new(Mutex::new(Vec::new()));
+        let reasons_clone = reasons.clone();
+        done.set_on_done_with(move |reason| reasons_clone.lock().unwrap().push(reason));
+
+        handle.abort();
+        assert_eq!(done.next(), None);
+
+        assert_eq!(*reasons.lock().unwrap(), vec![DoneReason::Aborted]);
+    }
+
+    #[test]
+    fn legacy_set_on_done_still_fires_regardless_of_reason() {
+        let signal = Arc::new(AtomicUsize::new(1));
+        let mut done = Done::new(TestSource::new(vec![1.0]), signal);
+        let called = Arc::new(AtomicBool::new(false));
+        let called_clone = called.clone();
+        done.set_on_done(move || called_clone.store(true, Ordering::SeqCst));
+
+        assert_eq!(done.next(), Some(1.0));
+        assert_eq!(done.next(), None);
+
+        assert!(called.load(Ordering::SeqCst));
+    }
+}
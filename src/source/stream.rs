@@ -0,0 +1,339 @@
+//! Adapts a [`Source`] into a [`futures_core::Stream`], opening it up to the
+//! combinator vocabulary popularized by `futures`/`tokio-stream`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+
+use crate::Source;
+
+/// Extension trait adding [`into_stream`](SourceStreamExt::into_stream) to
+/// every [`Source`].
+pub trait SourceStreamExt: Source {
+    /// Wraps `self` in a [`futures_core::Stream`] adapter.
+    ///
+    /// Because sources are synchronous pull iterators, the returned stream
+    /// never actually waits: every `poll_next` call immediately resolves to
+    /// `self.next()`.
+    #[inline]
+    fn into_stream(self) -> ToStream<Self>
+    where
+        Self: Sized,
+    {
+        ToStream::new(self)
+    }
+}
+
+impl<S: Source> SourceStreamExt for S {}
+
+/// Stream adapter returned by [`SourceStreamExt::into_stream`].
+///
+/// Carries the wrapped source's `channels()`/`sample_rate()` along so
+/// callers can reconstruct format information after applying stream
+/// combinators that would otherwise erase it.
+#[derive(Debug, Clone)]
+pub struct ToStream<S> {
+    input: S,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl<S: Source> ToStream<S> {
+    #[inline]
+    fn new(input: S) -> Self {
+        let channels = input.channels();
+        let sample_rate = input.sample_rate();
+        ToStream {
+            input,
+            channels,
+            sample_rate,
+        }
+    }
+
+    /// Number of channels of the wrapped source.
+    #[inline]
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Sample rate of the wrapped source.
+    #[inline]
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the wrapped source.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.input
+    }
+}
+
+impl<S> Stream for ToStream<S>
+where
+    S: Source + Unpin,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.input.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+/// Extension trait providing [`chunks_timeout`](ChunksTimeoutExt::chunks_timeout)
+/// on any [`Stream`], rodio's equivalent of `tokio_stream::StreamExt::chunks_timeout`
+/// for batching sample streams.
+pub trait ChunksTimeoutExt: Stream {
+    /// Batches items into `Vec`s of at most `cap` items, flushing whatever
+    /// has accumulated so far once `duration` has elapsed since the batch
+    /// started. Useful for handing fixed-size buffers to async sinks or
+    /// network encoders without stalling on a track that goes quiet.
+    ///
+    /// This module stays agnostic of any particular async runtime, so the
+    /// timer itself isn't built in: `make_sleep` is called with `duration`
+    /// each time a new batch starts and must return a future that resolves
+    /// after it elapses, e.g. `tokio::time::sleep` or
+    /// `async_io::Timer::after`.
+    ///
+    /// The timeout only ever has a chance to fire while waiting on an
+    /// upstream poll that returns [`Poll::Pending`]. A [`ToStream`] is
+    /// always immediately ready, so `source.into_stream().chunks_timeout(..)`
+    /// will only ever flush on a full batch or end-of-stream, never on the
+    /// timeout; put an adapter that can genuinely return `Pending` (a
+    /// network send, a bounded channel, backpressure from a slow consumer)
+    /// between `into_stream()` and `chunks_timeout` for the timeout to have
+    /// any effect.
+    #[inline]
+    fn chunks_timeout<F, Sl>(
+        self,
+        cap: usize,
+        duration: Duration,
+        make_sleep: F,
+    ) -> ChunksTimeout<Self, F, Sl>
+    where
+        Self: Sized + Unpin,
+        F: FnMut(Duration) -> Sl,
+        Sl: Future<Output = ()>,
+    {
+        ChunksTimeout::new(self, cap, duration, make_sleep)
+    }
+}
+
+impl<S: Stream> ChunksTimeoutExt for S {}
+
+/// Stream adapter returned by [`ChunksTimeoutExt::chunks_timeout`].
+pub struct ChunksTimeout<S, F, Sl>
+where
+    S: Stream,
+{
+    stream: S,
+    cap: usize,
+    duration: Duration,
+    make_sleep: F,
+    buffer: Vec<<S as Stream>::Item>,
+    // Armed only while `buffer` is non-empty, and (re-)created from
+    // `duration` the moment the buffer goes empty -> non-empty, so the
+    // deadline is always measured from when the current batch started, not
+    // from whenever the previous batch happened to flush.
+    sleep: Option<Pin<Box<Sl>>>,
+}
+
+impl<S, F, Sl> ChunksTimeout<S, F, Sl>
+where
+    S: Stream,
+    F: FnMut(Duration) -> Sl,
+    Sl: Future<Output = ()>,
+{
+    #[inline]
+    fn new(stream: S, cap: usize, duration: Duration, make_sleep: F) -> Self {
+        ChunksTimeout {
+            stream,
+            cap,
+            duration,
+            make_sleep,
+            buffer: Vec::with_capacity(cap),
+            sleep: None,
+        }
+    }
+}
+
+impl<S, F, Sl> Stream for ChunksTimeout<S, F, Sl>
+where
+    S: Stream + Unpin,
+    S::Item: Unpin,
+    F: FnMut(Duration) -> Sl + Unpin,
+    Sl: Future<Output = ()>,
+{
+    type Item = Vec<S::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if this.buffer.is_empty() {
+                        this.sleep = Some(Box::pin((this.make_sleep)(this.duration)));
+                    }
+                    this.buffer.push(item);
+                    if this.buffer.len() >= this.cap {
+                        this.sleep = None;
+                        return Poll::Ready(Some(std::mem::replace(
+                            &mut this.buffer,
+                            Vec::with_capacity(this.cap),
+                        )));
+                    }
+                }
+                Poll::Ready(None) => {
+                    this.sleep = None;
+                    return if this.buffer.is_empty() {
+                        Poll::Ready(None)
+                    } else {
+                        Poll::Ready(Some(std::mem::take(&mut this.buffer)))
+                    };
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        if this.buffer.is_empty() {
+            return Poll::Pending;
+        }
+
+        let sleep = this
+            .sleep
+            .as_mut()
+            .expect("sleep is armed whenever buffer is non-empty");
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                this.sleep = None;
+                Poll::Ready(Some(std::mem::replace(
+                    &mut this.buffer,
+                    Vec::with_capacity(this.cap),
+                )))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    #[derive(Clone)]
+    struct TestSource {
+        samples: std::vec::IntoIter<i16>,
+    }
+
+    impl TestSource {
+        fn new(samples: Vec<i16>) -> Self {
+            TestSource {
+                samples: samples.into_iter(),
+            }
+        }
+    }
+
+    impl Iterator for TestSource {
+        type Item = i16;
+
+        fn next(&mut self) -> Option<i16> {
+            self.samples.next()
+        }
+    }
+
+    impl Source for TestSource {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+
+        fn channels(&self) -> u16 {
+            2
+        }
+
+        fn sample_rate(&self) -> u32 {
+            44_100
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn into_stream_yields_source_samples() {
+        let source = TestSource::new(vec![1, 2, 3]);
+        let stream = source.into_stream();
+        assert_eq!(stream.channels(), 2);
+        assert_eq!(stream.sample_rate(), 44_100);
+        let collected: Vec<i16> = stream.collect().await;
+        assert_eq!(collected, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn chunks_timeout_flushes_full_and_partial_batches() {
+        let source = TestSource::new(vec![1, 2, 3, 4, 5]);
+        let stream = source
+            .into_stream()
+            .chunks_timeout(2, Duration::from_secs(60), tokio::time::sleep);
+        let chunks: Vec<Vec<i16>> = stream.collect().await;
+        assert_eq!(chunks, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    }
+
+    /// A test-only [`Stream`] that, unlike [`ToStream`], can actually return
+    /// [`Poll::Pending`] -- needed to reach `ChunksTimeout`'s timeout-flush
+    /// branch at all (see the caveat on [`ChunksTimeoutExt::chunks_timeout`]).
+    /// Yields each queued item in turn, then stays `Pending` forever,
+    /// simulating an upstream that is still waiting on more data.
+    struct ManualStream {
+        items: std::collections::VecDeque<i16>,
+    }
+
+    impl ManualStream {
+        fn new(items: Vec<i16>) -> Self {
+            ManualStream {
+                items: items.into(),
+            }
+        }
+    }
+
+    impl Stream for ManualStream {
+        type Item = i16;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<i16>> {
+            match self.items.pop_front() {
+                Some(item) => Poll::Ready(Some(item)),
+                None => Poll::Pending,
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn chunks_timeout_measures_duration_from_first_item_of_batch() {
+        let duration = Duration::from_millis(100);
+
+        let upstream = ManualStream::new(vec![1]);
+        let mut stream = Box::pin(upstream.chunks_timeout(10, duration, tokio::time::sleep));
+
+        // Let most of `duration` elapse after the adapter is built but
+        // *before* the batch starts. If the timer were armed at
+        // adapter-construction time instead of when the first item lands,
+        // the chunk below would be flushed almost immediately instead of
+        // after a full `duration`.
+        tokio::time::sleep(duration - Duration::from_millis(1)).await;
+
+        let start = tokio::time::Instant::now();
+        let chunk = stream.next().await;
+        assert_eq!(chunk, Some(vec![1]));
+        assert!(tokio::time::Instant::now() - start >= duration);
+    }
+}